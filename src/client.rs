@@ -9,6 +9,7 @@ use crate::voice::{encode_voice_packet, Clientbound, VoicePacket};
 use crate::ServerState;
 use bytes::BytesMut;
 use protobuf::Message;
+use sha2::{Digest, Sha256};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU32, Ordering};
@@ -37,13 +38,33 @@ pub struct Client {
     pub publisher: Sender<ClientMessage>,
     pub targets: Vec<Arc<RwLock<VoiceTarget>>>,
     pub last_ping: RwLock<Instant>,
+    /// SHA-256 fingerprint of the peer's leaf certificate, hex-encoded.
+    ///
+    /// This is the same "certificate hash" Mumble uses to recognise a
+    /// registered user across reconnects. `None` for anonymous, token-only
+    /// connections that presented no client certificate.
+    pub cert_hash: Option<String>,
+}
+
+/// Compute the hex-encoded SHA-256 fingerprint of a DER-encoded certificate.
+fn cert_fingerprint(cert: &tokio_rustls::rustls::Certificate) -> String {
+    use std::fmt::Write;
+
+    let digest = Sha256::digest(&cert.0);
+    let mut hash = String::with_capacity(digest.len() * 2);
+
+    for byte in digest.iter() {
+        let _ = write!(hash, "{:02x}", byte);
+    }
+
+    hash
 }
 
 impl Client {
     pub async fn init(
         stream: &mut TlsStream<TcpStream>,
         server_version: Version,
-    ) -> Result<(Version, Authenticate, CryptState), MumbleError> {
+    ) -> Result<(Version, Authenticate, CryptState, Option<String>), MumbleError> {
         let version: Version = expected_message(MessageKind::Version, stream, 0).await?;
 
         // Send version
@@ -52,13 +73,22 @@ impl Client {
         // Get authenticate
         let authenticate: Authenticate = expected_message(MessageKind::Authenticate, stream, 0).await?;
 
+        // Grab the leaf of the peer certificate chain (if the client presented one
+        // during the handshake) and hash it into a stable per-user identity.
+        let cert_hash = stream
+            .get_ref()
+            .1
+            .peer_certificates()
+            .and_then(|chain| chain.first())
+            .map(cert_fingerprint);
+
         let crypt = CryptState::default();
         let crypt_setup = crypt.get_crypt_setup();
 
         // Send crypt setup
         send_message(MessageKind::CryptSetup, &crypt_setup, stream).await?;
 
-        Ok((version, authenticate, crypt))
+        Ok((version, authenticate, crypt, cert_hash))
     }
 
     pub fn new(
@@ -70,6 +100,7 @@ impl Client {
         write: WriteHalf<TlsStream<TcpStream>>,
         udp_socket: Arc<UdpSocket>,
         publisher: Sender<ClientMessage>,
+        cert_hash: Option<String>,
     ) -> Self {
         let tokens = authenticate.get_tokens().iter().map(|token| token.to_string()).collect();
         let capacity = unwrap_ctx!(parse_usize(&std::env::var("CLIENT_CAPACITY").unwrap_or("2048".to_string())));
@@ -93,6 +124,7 @@ impl Client {
             publisher,
             targets,
             last_ping: RwLock::new(Instant::now()),
+            cert_hash,
         }
     }
 
@@ -264,6 +296,13 @@ impl Client {
         user_state.set_session(self.session_id);
         user_state.set_name(self.authenticate.get_username().to_string());
 
+        // Surface the certificate fingerprint through the standard Mumble `hash`
+        // field so clients and the admin API can tell a returning registered user
+        // apart from an anonymous, token-only connection.
+        if let Some(cert_hash) = &self.cert_hash {
+            user_state.set_hash(cert_hash.clone());
+        }
+
         user_state
     }
 }
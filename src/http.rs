@@ -0,0 +1,164 @@
+use crate::state::ServerState;
+use crate::sync::RwLock;
+use actix_web::dev::ServiceRequest;
+use actix_web::{web, App, HttpResponse, HttpServer};
+use actix_web_httpauth::extractors::basic::BasicAuth;
+use actix_web_httpauth::middleware::HttpAuthentication;
+use serde::Serialize;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio_rustls::rustls::ServerConfig;
+
+/// Basic-auth credentials shared by every admin route, installed as app data so
+/// the authentication middleware can compare the presented credentials.
+struct AdminAuth {
+    user: String,
+    password: String,
+}
+
+/// A connected user as exposed by `GET /users`. Mirrors the shape of the external
+/// stats API so dashboards can poll live state instead of scraping Prometheus.
+#[derive(Serialize)]
+struct UserInfo {
+    session: u32,
+    name: String,
+    channel_id: u32,
+    mute: bool,
+    deaf: bool,
+    use_opus: bool,
+    udp_addr: Option<String>,
+    udp: bool,
+}
+
+/// A channel as exposed by `GET /channels`.
+#[derive(Serialize)]
+struct ChannelInfo {
+    id: u32,
+    name: String,
+    members: usize,
+}
+
+async fn validate_credentials(req: ServiceRequest, credentials: BasicAuth) -> Result<ServiceRequest, (actix_web::Error, ServiceRequest)> {
+    let auth = match req.app_data::<web::Data<AdminAuth>>() {
+        Some(auth) => auth,
+        None => return Err((actix_web::error::ErrorUnauthorized("unauthorized"), req)),
+    };
+
+    let password = credentials.password().unwrap_or("");
+
+    if credentials.user_id() == auth.user && password == auth.password {
+        Ok(req)
+    } else {
+        Err((actix_web::error::ErrorUnauthorized("unauthorized"), req))
+    }
+}
+
+async fn get_users(state: web::Data<Arc<RwLock<ServerState>>>) -> HttpResponse {
+    let state = match state.read_err().await {
+        Ok(state) => state,
+        Err(e) => return HttpResponse::InternalServerError().body(e.to_string()),
+    };
+
+    let mut users = Vec::with_capacity(state.clients.len());
+
+    for client in state.clients.values() {
+        let client = match client.read_err().await {
+            Ok(client) => client,
+            Err(_) => continue,
+        };
+
+        users.push(UserInfo {
+            session: client.session_id,
+            name: client.authenticate.get_username().to_string(),
+            channel_id: client.channel_id.load(Ordering::Relaxed),
+            mute: client.mute,
+            deaf: client.deaf,
+            use_opus: client.use_opus,
+            udp_addr: client.udp_socket_addr.map(|addr| addr.to_string()),
+            udp: client.udp_socket_addr.is_some(),
+        });
+    }
+
+    HttpResponse::Ok().json(users)
+}
+
+async fn get_channels(state: web::Data<Arc<RwLock<ServerState>>>) -> HttpResponse {
+    let state = match state.read_err().await {
+        Ok(state) => state,
+        Err(e) => return HttpResponse::InternalServerError().body(e.to_string()),
+    };
+
+    let mut channels = Vec::with_capacity(state.channels.len());
+
+    for channel in state.channels.values() {
+        let channel_state = match channel.read_err().await {
+            Ok(channel) => channel.get_channel_state(),
+            Err(_) => continue,
+        };
+
+        let id = channel_state.get_channel_id();
+        let mut members = 0;
+
+        for client in state.clients.values() {
+            if let Ok(client) = client.read_err().await {
+                if client.channel_id.load(Ordering::Relaxed) == id {
+                    members += 1;
+                }
+            }
+        }
+
+        channels.push(ChannelInfo {
+            id,
+            name: channel_state.get_name().to_string(),
+            members,
+        });
+    }
+
+    HttpResponse::Ok().json(channels)
+}
+
+pub fn create_http_server(
+    listen: String,
+    tls_config: ServerConfig,
+    https: bool,
+    state: Arc<RwLock<ServerState>>,
+    user: String,
+    password: String,
+) -> Option<JoinHandle<()>> {
+    let auth = web::Data::new(AdminAuth { user, password });
+    let state = web::Data::new(state);
+
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(auth.clone())
+            .app_data(state.clone())
+            .wrap(HttpAuthentication::basic(validate_credentials))
+            .route("/users", web::get().to(get_users))
+            .route("/channels", web::get().to(get_channels))
+    });
+
+    let server = if https {
+        match server.bind_rustls(&listen, tls_config) {
+            Ok(server) => server,
+            Err(e) => {
+                tracing::error!("cannot bind https admin server: {}", e);
+                return None;
+            }
+        }
+    } else {
+        match server.bind(&listen) {
+            Ok(server) => server,
+            Err(e) => {
+                tracing::error!("cannot bind http admin server: {}", e);
+                return None;
+            }
+        }
+    };
+
+    Some(actix_rt::spawn(async move {
+        if let Err(e) = server.run().await {
+            tracing::error!("http admin server error: {}", e);
+        }
+    }))
+}
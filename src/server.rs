@@ -0,0 +1,140 @@
+use crate::handler::{handle_client, handle_voice_packet};
+use crate::proto::mumble::Version;
+use crate::state::ServerState;
+use crate::sync::RwLock;
+use std::sync::Arc;
+use tokio::net::{TcpListener, UdpSocket};
+use tokio::task::JoinHandle;
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+use arc_swap::ArcSwap;
+
+/// Maximum size of a Mumble UDP datagram we are willing to read in one go.
+const MAX_UDP_PACKET: usize = 1024;
+
+/// Length of the unauthenticated ping request Mumble clients and server-browser
+/// tools send to an unconnected server: a 4-byte request type (zero) followed by
+/// an 8-byte client identifier.
+const PING_REQUEST_LEN: usize = 12;
+
+/// Max users advertised in the ping response, matching `send_server_config`.
+const MAX_USERS: u32 = 2048;
+
+/// Allowed bandwidth advertised in the ping response, matching the server sync.
+const ALLOWED_BANDWIDTH: u32 = 72000;
+
+/// Build the 24-byte response to an unauthenticated UDP ping so server browsers
+/// and monitoring tools can display live stats without a full connection. The
+/// identifier from the request is echoed back verbatim so the client can match
+/// the reply to its probe.
+fn build_ping_response(version: u32, ident: &[u8], users: u32) -> [u8; 24] {
+    let mut response = [0u8; 24];
+
+    response[0..4].copy_from_slice(&version.to_be_bytes());
+    response[4..12].copy_from_slice(ident);
+    response[12..16].copy_from_slice(&users.to_be_bytes());
+    response[16..20].copy_from_slice(&MAX_USERS.to_be_bytes());
+    response[20..24].copy_from_slice(&ALLOWED_BANDWIDTH.to_be_bytes());
+
+    response
+}
+
+pub async fn create_udp_server(version: u32, socket: Arc<UdpSocket>, state: Arc<RwLock<ServerState>>) {
+    let mut buf = [0u8; MAX_UDP_PACKET];
+
+    loop {
+        let (size, addr) = match socket.recv_from(&mut buf).await {
+            Ok(result) => result,
+            Err(e) => {
+                tracing::error!("udp recv error: {}", e);
+                continue;
+            }
+        };
+
+        // Always let the voice handler attempt the crypt-match: it resolves the
+        // owning client and registers the sender's address on the first packet, so
+        // it must run for datagrams from addresses we have not seen yet. It returns
+        // `false` when no connected client's crypt/session claims the datagram.
+        let handled = match handle_voice_packet(&state, &socket, addr, &buf[..size]).await {
+            Ok(handled) => handled,
+            Err(e) => {
+                tracing::error!("error handling voice packet from {}: {}", addr, e);
+                continue;
+            }
+        };
+
+        // An unclaimed 12-byte zero-type datagram is the unauthenticated ping that
+        // server browsers and monitoring tools send to an unconnected server.
+        if !handled && size == PING_REQUEST_LEN && buf[0..4] == [0u8; 4] {
+            let users = match state.read_err().await {
+                Ok(state) => state.clients.len() as u32,
+                Err(_) => 0,
+            };
+
+            let response = build_ping_response(version, &buf[4..12], users);
+
+            if let Err(e) = socket.send_to(&response, addr).await {
+                tracing::error!("cannot answer udp ping from {}: {}", addr, e);
+            }
+        }
+    }
+}
+
+pub fn create_tcp_server(
+    listener: TcpListener,
+    acceptor: Arc<ArcSwap<ServerConfig>>,
+    server_version: Version,
+    state: Arc<RwLock<ServerState>>,
+) -> JoinHandle<()> {
+    actix_rt::spawn(async move {
+        loop {
+            let (stream, _) = match listener.accept().await {
+                Ok(result) => result,
+                Err(e) => {
+                    tracing::error!("tcp accept error: {}", e);
+                    continue;
+                }
+            };
+
+            // Load the live TLS config per handshake so a reloaded certificate is
+            // picked up by new connections while existing sessions keep theirs.
+            let acceptor = TlsAcceptor::from(acceptor.load_full());
+            let server_version = server_version.clone();
+            let state = state.clone();
+
+            actix_rt::spawn(async move {
+                let stream = match acceptor.accept(stream).await {
+                    Ok(stream) => stream,
+                    Err(e) => {
+                        tracing::error!("tls handshake error: {}", e);
+                        return;
+                    }
+                };
+
+                if let Err(e) = handle_client(stream, server_version, state).await {
+                    tracing::error!("error handling client: {}", e);
+                }
+            });
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ping_response_layout() {
+        let version = 1 << 16 | 2 << 8 | 4;
+        let ident = [1u8, 2, 3, 4, 5, 6, 7, 8];
+
+        let response = build_ping_response(version, &ident, 7);
+
+        assert_eq!(&response[0..4], &version.to_be_bytes());
+        assert_eq!(&response[4..12], &ident);
+        assert_eq!(&response[12..16], &7u32.to_be_bytes());
+        assert_eq!(&response[16..20], &2048u32.to_be_bytes());
+        assert_eq!(&response[20..24], &72000u32.to_be_bytes());
+    }
+}